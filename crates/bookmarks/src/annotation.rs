@@ -1,4 +1,4 @@
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 
 use editor::{Editor, ToPoint};
 use gpui::{
@@ -8,8 +8,11 @@ use gpui::{
 use log::info;
 use project::Bookmark;
 use ui::{div, h_flex, rems, v_flex, ActiveTheme, StyledExt, ViewContext, WindowContext};
+use util::ResultExt;
 use workspace::{item::ItemHandle, ModalView, Workspace};
 
+use crate::persistence::BOOKMARKS_DB;
+
 actions!(annotation, [Confirm]);
 
 // FIXME: code duplication
@@ -34,18 +37,32 @@ impl Render for Annotation {
 pub struct AnnotationView {
     editor: View<Editor>,
     workspace: WeakView<Workspace>,
+    // `Some(ix)` when editing an existing bookmark in the project's
+    // bookmark collection, `None` when adding a new one.
+    editing: Option<usize>,
 }
 
 impl ModalView for AnnotationView {}
 impl EventEmitter<DismissEvent> for AnnotationView {}
 
 impl AnnotationView {
-    fn new(cx: &mut ViewContext<Self>, workspace: WeakView<Workspace>) -> Self {
+    fn new(
+        cx: &mut ViewContext<Self>,
+        workspace: WeakView<Workspace>,
+        editing: Option<(usize, String)>,
+    ) -> Self {
         cx.bind_keys([KeyBinding::new("enter", Confirm, None)]);
 
+        let editor = create_editor(Arc::from("Add Bookmark..."), cx);
+        let editing = editing.map(|(bookmark_ix, label)| {
+            editor.update(cx, |editor, cx| editor.set_text(label, cx));
+            bookmark_ix
+        });
+
         Self {
-            editor: create_editor(Arc::from("Add Bookmark..."), cx),
+            editor,
             workspace,
+            editing,
         }
     }
 
@@ -55,17 +72,46 @@ impl AnnotationView {
 
     fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
         let bookmark_label = self.editor.read(cx).text(cx);
-        info!("New Bookmark with Annotation {}", bookmark_label);
 
         if let Some(workspace) = self.workspace.upgrade() {
             workspace.update(cx, |workspace, cx| {
-                let project = workspace.project();
+                let project = workspace.project().clone();
+
+                if let Some(bookmark_ix) = self.editing {
+                    info!("Updating bookmark with annotation {}", bookmark_label);
+
+                    let bookmark_id = project.update(cx, |project, cx| {
+                        project.bookmarks_mut().update(cx, |bookmarks, _cx| {
+                            let bookmark = bookmarks.get_mut(bookmark_ix)?;
+                            bookmark.set_label(&bookmark_label);
+                            bookmark.id()
+                        })
+                    });
+
+                    if let Some(bookmark_id) = bookmark_id {
+                        cx.background_executor()
+                            .spawn(async move {
+                                BOOKMARKS_DB
+                                    .update_bookmark_label(bookmark_id, bookmark_label)
+                                    .await
+                                    .log_err();
+                            })
+                            .detach();
+                    }
+
+                    cx.notify();
+                    return;
+                }
+
+                info!("New Bookmark with Annotation {}", bookmark_label);
+
+                let workspace_id = workspace.database_id();
                 if let Some(editor) = workspace.active_item_as::<Editor>(cx) {
-                    let point = editor.update(cx, |editor, cx| {
+                    let (anchor, point) = editor.update(cx, |editor, cx| {
                         let snapshot = editor.snapshot(cx).display_snapshot.buffer_snapshot;
                         let cursor_position = editor.selections.newest_anchor().head();
                         let point = cursor_position.to_point(&snapshot);
-                        point
+                        (cursor_position.text_anchor, point)
                     });
 
                     if let Some(path) = editor.project_path(cx) {
@@ -75,17 +121,64 @@ impl AnnotationView {
                             point.row
                         );
 
+                        let abs_path = project
+                            .read(cx)
+                            .absolute_path(&path, cx)
+                            .unwrap_or_else(|| path.path.to_path_buf());
+
                         project.update(cx, |project, cx| {
                             project.bookmarks_mut().update(cx, |bookmarks, _cx| {
                                 bookmarks.push(Bookmark::new(
                                     &bookmark_label,
-                                    path,
-                                    // TODO: add absolute path
-                                    PathBuf::from("/tmp/tmp.rs"),
+                                    path.clone(),
+                                    abs_path.clone(),
+                                    anchor,
                                     point,
                                 ));
                             })
                         });
+
+                        if let Some(workspace_id) = workspace_id {
+                            let new_bookmark_path = path.clone();
+                            let relative_path = path.path.to_path_buf();
+
+                            cx.spawn(|_, mut cx| async move {
+                                let bookmark_id = BOOKMARKS_DB
+                                    .save_bookmark(
+                                        workspace_id,
+                                        bookmark_label,
+                                        relative_path,
+                                        abs_path,
+                                        point.row,
+                                        point.column,
+                                    )
+                                    .await
+                                    .log_err()?;
+
+                                project
+                                    .update(&mut cx, |project, cx| {
+                                        project.bookmarks_mut().update(cx, |bookmarks, _cx| {
+                                            // Look the new bookmark back up by its anchor
+                                            // rather than carrying its index across the
+                                            // await above: another bookmark may have been
+                                            // deleted in the meantime, shifting indices out
+                                            // from under us.
+                                            if let Some(bookmark) =
+                                                bookmarks.iter_mut().find(|bookmark| {
+                                                    bookmark.project_path() == &new_bookmark_path
+                                                        && bookmark.anchor() == anchor
+                                                })
+                                            {
+                                                bookmark.set_id(bookmark_id);
+                                            }
+                                        })
+                                    })
+                                    .log_err();
+
+                                Some(())
+                            })
+                            .detach();
+                        }
                     }
                     cx.notify();
                 }
@@ -96,10 +189,29 @@ impl AnnotationView {
     }
 
     pub fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let weak_workspace = cx.view().downgrade();
+        workspace.toggle_modal(cx, |cx| AnnotationView::new(cx, weak_workspace, None));
+    }
+
+    /// Opens the annotation editor pre-populated with an existing
+    /// bookmark's label, so confirming updates it in place instead of
+    /// creating a duplicate.
+    pub fn edit(workspace: &mut Workspace, bookmark_ix: usize, cx: &mut ViewContext<Workspace>) {
+        let label = workspace
+            .project()
+            .read(cx)
+            .bookmarks()
+            .read(cx)
+            .get(bookmark_ix)
+            .map(|bookmark| bookmark.label().clone());
+
+        let Some(label) = label else {
+            return;
+        };
+
         let weak_workspace = cx.view().downgrade();
         workspace.toggle_modal(cx, |cx| {
-            let view = AnnotationView::new(cx, weak_workspace);
-            view
+            AnnotationView::new(cx, weak_workspace, Some((bookmark_ix, label)))
         });
     }
 }
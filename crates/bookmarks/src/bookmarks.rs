@@ -8,24 +8,87 @@ use gpui::{
 use log::info;
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
-use project::Project;
+use project::{Bookmark, Project, ProjectPath};
 use std::sync::Arc;
-use text::Bias;
+use text::{Anchor, Bias, Point, ToPoint};
 use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
 use util::ResultExt;
 use workspace::{ModalView, Workspace};
 
+use persistence::BOOKMARKS_DB;
+
 mod annotation;
+mod gutter;
+mod persistence;
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(BookmarkView::register).detach();
+    cx.observe_new_views(gutter::register).detach();
+}
+
+/// Resolves `target`'s current position in `editor`'s buffer, binding its
+/// anchor for the first time if it was only just restored from
+/// `BOOKMARKS_DB`, and persists the resolved row/column so a later reload
+/// doesn't show the stale position. `target` is looked up by its
+/// `(project_path, point)` identity rather than a raw index, since an
+/// index captured before an `await` can point at an unrelated bookmark by
+/// the time this runs if another one was deleted in the meantime.
+pub(crate) fn resolve_and_rebind(
+    project: &Model<Project>,
+    target_path: &ProjectPath,
+    target_point: Point,
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+) -> Option<Point> {
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let buffer_snapshot = buffer.read(cx).snapshot();
+
+    let (bookmark_id, point, moved) = project.update(cx, |project, cx| {
+        project.bookmarks_mut().update(cx, |bookmarks, _cx| {
+            let bookmark = bookmarks.iter_mut().find(|bookmark| {
+                bookmark.project_path() == target_path && bookmark.point() == target_point
+            })?;
+
+            // Bookmarks restored from `BOOKMARKS_DB` don't have a live
+            // anchor yet: bind one now that the buffer is open.
+            let anchor = if bookmark.anchor() == Anchor::MIN {
+                buffer_snapshot.anchor_before(bookmark.point())
+            } else {
+                bookmark.anchor()
+            };
+
+            let point = anchor.to_point(&buffer_snapshot);
+            let moved = point != bookmark.point();
+            bookmark.rebind(anchor, point);
+            Some((bookmark.id(), point, moved))
+        })
+    })?;
+
+    if let (true, Some(bookmark_id)) = (moved, bookmark_id) {
+        cx.background_executor()
+            .spawn(async move {
+                BOOKMARKS_DB
+                    .update_bookmark_position(bookmark_id, point.row, point.column)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    Some(point)
 }
 
 pub struct BookmarkView {
     picker: View<Picker<BookmarkViewDelegate>>,
 }
 
-actions!(bookmarks, [Toggle, AddBookmark]);
+actions!(bookmarks, [Toggle, AddBookmark, NextBookmark, PrevBookmark]);
+
+#[derive(Clone, Copy)]
+enum Direction {
+    Next,
+    Prev,
+}
 
 impl EventEmitter<DismissEvent> for BookmarkView {}
 
@@ -38,7 +101,7 @@ impl FocusableView for BookmarkView {
 impl ModalView for BookmarkView {}
 
 impl BookmarkView {
-    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
         workspace.register_action(|workspace, _: &Toggle, cx| {
             let Some(bookmarks) = workspace.active_modal::<Self>(cx) else {
                 Self::open(workspace, cx);
@@ -55,6 +118,138 @@ impl BookmarkView {
         workspace.register_action(|workspace, _: &AddBookmark, cx| {
             AnnotationView::open(workspace, cx);
         });
+
+        workspace.register_action(|workspace, _: &NextBookmark, cx| {
+            Self::jump_to_bookmark(workspace, Direction::Next, cx);
+        });
+
+        workspace.register_action(|workspace, _: &PrevBookmark, cx| {
+            Self::jump_to_bookmark(workspace, Direction::Prev, cx);
+        });
+
+        Self::load_bookmarks(workspace, cx);
+    }
+
+    fn jump_to_bookmark(
+        workspace: &mut Workspace,
+        direction: Direction,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        if project.read(cx).bookmarks().read(cx).is_empty() {
+            return;
+        }
+
+        let current = workspace.active_item_as::<Editor>(cx).and_then(|editor| {
+            let path = editor.project_path(cx)?;
+            let (point, buffer_snapshot) = editor.update(cx, |editor, cx| {
+                let snapshot = editor.snapshot(cx).display_snapshot.buffer_snapshot;
+                let point = editor.selections.newest_anchor().head().to_point(&snapshot);
+                let buffer_snapshot = editor
+                    .buffer()
+                    .read(cx)
+                    .as_singleton()
+                    .map(|buffer| buffer.read(cx).snapshot());
+                (point, buffer_snapshot)
+            });
+
+            // Resolve this file's bookmarks through the live buffer before
+            // ordering against them below, so one that moved due to edits
+            // above it sorts and compares at its current row instead of a
+            // stale persisted one, matching the live cursor position above.
+            // This has to land on the project's own bookmarks (not a local
+            // clone), since `target_point` below is later looked back up
+            // against them by identity.
+            if let Some(buffer_snapshot) = buffer_snapshot {
+                project.update(cx, |project, cx| {
+                    project.bookmarks_mut().update(cx, |bookmarks, _cx| {
+                        for bookmark in bookmarks.iter_mut() {
+                            if bookmark.project_path() == &path && bookmark.anchor() != Anchor::MIN
+                            {
+                                let resolved = bookmark.anchor().to_point(&buffer_snapshot);
+                                bookmark.rebind(bookmark.anchor(), resolved);
+                            }
+                        }
+                    })
+                });
+            }
+
+            Some((path, point))
+        });
+
+        let bookmarks = project.read(cx).bookmarks().read(cx).clone();
+
+        let Some(bookmark) = next_bookmark(&bookmarks, current.as_ref(), direction).cloned()
+        else {
+            return;
+        };
+        let target_path = bookmark.project_path().clone();
+        let target_point = bookmark.point();
+
+        let open_task = workspace.open_path(bookmark.project_path().clone(), None, true, cx);
+        cx.spawn(|_, mut cx| async move {
+            let item = open_task.await.log_err()?;
+
+            if let Some(active_editor) = item.downcast::<Editor>() {
+                active_editor
+                    .downgrade()
+                    .update(&mut cx, |editor, cx| {
+                        let Some(point) =
+                            resolve_and_rebind(&project, &target_path, target_point, editor, cx)
+                        else {
+                            return;
+                        };
+                        let point = editor
+                            .snapshot(cx)
+                            .display_snapshot
+                            .buffer_snapshot
+                            .clip_point(point, Bias::Left);
+                        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                            s.select_ranges([point..point])
+                        });
+                    })
+                    .log_err();
+            }
+
+            Some(())
+        })
+        .detach();
+    }
+
+    fn load_bookmarks(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(workspace_id) = workspace.database_id() else {
+            return;
+        };
+        let project = workspace.project().clone();
+
+        cx.spawn(|_, mut cx| async move {
+            let saved_bookmarks = BOOKMARKS_DB.bookmarks(workspace_id).await.log_err()?;
+
+            project
+                .update(&mut cx, |project, cx| {
+                    for (id, label, _project_path, abs_path, row, column) in saved_bookmarks {
+                        let Some(project_path) = project.find_project_path(&abs_path, cx) else {
+                            continue;
+                        };
+
+                        let mut bookmark = Bookmark::from_persisted_point(
+                            &label,
+                            project_path,
+                            abs_path,
+                            Point::new(row, column),
+                        );
+                        bookmark.set_id(id);
+
+                        project.bookmarks_mut().update(cx, |bookmarks, _cx| {
+                            bookmarks.push(bookmark);
+                        });
+                    }
+                })
+                .log_err();
+
+            Some(())
+        })
+        .detach();
     }
 
     fn open(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
@@ -74,6 +269,88 @@ impl BookmarkView {
     }
 }
 
+/// The text fuzzy-matched against in the bookmarks picker: the label
+/// together with the file path, so a bookmark can be found by typing
+/// either.
+fn searchable_text(bookmark: &Bookmark) -> String {
+    format!(
+        "{} {}",
+        bookmark.label(),
+        bookmark.project_path().path.to_string_lossy()
+    )
+}
+
+/// Splits match positions into `searchable_text` back into the label's own
+/// positions and the path's own positions, so each `HighlightedLabel` only
+/// highlights the characters that belong to it.
+fn split_positions(label_len: usize, positions: &[usize]) -> (Vec<usize>, Vec<usize>) {
+    let mut label_positions = Vec::new();
+    let mut path_positions = Vec::new();
+
+    for &position in positions {
+        if position < label_len {
+            label_positions.push(position);
+        } else if position > label_len {
+            path_positions.push(position - label_len - 1);
+        }
+    }
+
+    (label_positions, path_positions)
+}
+
+/// Finds the bookmark that should be jumped to next, cycling through the
+/// current file's bookmarks first and then wrapping into the rest of the
+/// workspace's bookmarks, ordered by file path and then by line. Callers
+/// that need a fresher `point()` than what's persisted (e.g. edits made
+/// above a bookmark in the currently open file) should resolve it through
+/// the live buffer before calling this, since `bookmarks` is compared
+/// using whatever `point()` each entry already has.
+fn next_bookmark<'a>(
+    bookmarks: &'a [Bookmark],
+    current: Option<&(ProjectPath, Point)>,
+    direction: Direction,
+) -> Option<&'a Bookmark> {
+    let mut ordered = bookmarks.iter().collect::<Vec<_>>();
+    ordered.sort_by(|a, b| {
+        a.project_path()
+            .path
+            .cmp(&b.project_path().path)
+            .then(a.point().cmp(&b.point()))
+    });
+
+    let Some((path, point)) = current else {
+        return match direction {
+            Direction::Next => ordered.first().copied(),
+            Direction::Prev => ordered.last().copied(),
+        };
+    };
+
+    match direction {
+        Direction::Next => ordered
+            .iter()
+            .find(|bookmark| bookmark.project_path() == path && bookmark.point() > *point)
+            .or_else(|| {
+                ordered
+                    .iter()
+                    .find(|bookmark| bookmark.project_path().path > path.path)
+            })
+            .or_else(|| ordered.first())
+            .copied(),
+        Direction::Prev => ordered
+            .iter()
+            .rev()
+            .find(|bookmark| bookmark.project_path() == path && bookmark.point() < *point)
+            .or_else(|| {
+                ordered
+                    .iter()
+                    .rev()
+                    .find(|bookmark| bookmark.project_path().path < path.path)
+            })
+            .or_else(|| ordered.last())
+            .copied(),
+    }
+}
+
 impl Render for BookmarkView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         div()
@@ -112,6 +389,7 @@ impl BookmarkViewDelegate {
     fn delete_bookmark(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
         let bookmarks = self.project.read(cx).bookmarks().read(cx);
         let bookmark = &bookmarks[ix];
+        let bookmark_id = bookmark.id();
 
         info!("Deleting bookmark {}", bookmark.label());
         if let Some(workspace) = self.workspace.upgrade() {
@@ -125,15 +403,29 @@ impl BookmarkViewDelegate {
                 });
             });
 
+            if let Some(bookmark_id) = bookmark_id {
+                cx.background_executor()
+                    .spawn(async move {
+                        BOOKMARKS_DB.delete_bookmark(bookmark_id).await.log_err();
+                    })
+                    .detach();
+            }
+
             cx.spawn(move |this, mut cx| async move {
                 this.update(&mut cx, move |picker, cx| {
-                    picker.delegate.set_selected_index(ix - 1, cx);
+                    picker.delegate.set_selected_index(ix.saturating_sub(1), cx);
                     picker.update_matches(picker.query(cx), cx)
                 })
             })
             .detach();
         }
     }
+
+    fn edit_bookmark(&self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(workspace) = self.workspace.upgrade() {
+            workspace.update(cx, |workspace, cx| AnnotationView::edit(workspace, ix, cx));
+        }
+    }
 }
 
 impl PickerDelegate for BookmarkViewDelegate {
@@ -175,7 +467,7 @@ impl PickerDelegate for BookmarkViewDelegate {
             .read(cx)
             .iter()
             .enumerate()
-            .map(|(id, bookmark)| StringMatchCandidate::new(id, bookmark.label().clone()))
+            .map(|(id, bookmark)| StringMatchCandidate::new(id, searchable_text(bookmark)))
             .collect::<Vec<_>>();
 
         self.matches = smol::block_on(fuzzy::match_strings(
@@ -186,16 +478,10 @@ impl PickerDelegate for BookmarkViewDelegate {
             &Default::default(),
             cx.background_executor().clone(),
         ));
-        self.matches.sort_unstable_by_key(|m| m.candidate_id);
+        self.matches
+            .sort_unstable_by_key(|m| std::cmp::Reverse(OrderedFloat(m.score)));
 
-        self.selected_index = self
-            .matches
-            .iter()
-            .enumerate()
-            .rev()
-            .max_by_key(|(_, m)| OrderedFloat(m.score))
-            .map(|(ix, _)| ix)
-            .unwrap_or(0);
+        self.selected_index = 0;
 
         Task::ready(())
     }
@@ -205,10 +491,13 @@ impl PickerDelegate for BookmarkViewDelegate {
             if let Some(workspace) = self.workspace.upgrade() {
                 // FIXME: clone
                 let bookmark = self.project.read(cx).bookmarks().read(cx)[m.candidate_id].clone();
+                let target_path = bookmark.project_path().clone();
+                let target_point = bookmark.point();
                 let open_task = workspace.update(cx, |workspace, cx| {
                     workspace.open_path(bookmark.project_path().clone(), None, true, cx)
                 });
 
+                let project = self.project.clone();
                 let view = self.view.clone();
                 cx.spawn(|_, mut cx| async move {
                     let item = open_task.await.log_err()?;
@@ -218,10 +507,20 @@ impl PickerDelegate for BookmarkViewDelegate {
                         active_editor
                             .downgrade()
                             .update(&mut cx, |editor, cx| {
-                                let snapshot = editor.snapshot(cx).display_snapshot;
-                                let point = snapshot
+                                let Some(point) = resolve_and_rebind(
+                                    &project,
+                                    &target_path,
+                                    target_point,
+                                    editor,
+                                    cx,
+                                ) else {
+                                    return;
+                                };
+                                let point = editor
+                                    .snapshot(cx)
+                                    .display_snapshot
                                     .buffer_snapshot
-                                    .clip_point(bookmark.point(), Bias::Left);
+                                    .clip_point(point, Bias::Left);
                                 editor.change_selections(Some(Autoscroll::center()), cx, |s| {
                                     s.select_ranges([point..point])
                                 });
@@ -256,8 +555,11 @@ impl PickerDelegate for BookmarkViewDelegate {
             return None;
         }
 
-        let bookmark = &bookmarks[candidate.candidate_id];
+        let bookmark_ix = candidate.candidate_id;
+        let bookmark = &bookmarks[bookmark_ix];
         let path = Arc::clone(&bookmark.project_path().path);
+        let (label_positions, path_positions) =
+            split_positions(bookmark.label().chars().count(), &candidate.positions);
 
         Some(
             ListItem::new(ix)
@@ -267,17 +569,29 @@ impl PickerDelegate for BookmarkViewDelegate {
                 .child(
                     h_flex()
                         .gap_2()
-                        // FIXME: clone, highlighting
-                        .child(HighlightedLabel::new(bookmark.label().clone(), Vec::new()))
+                        // FIXME: clone
+                        .child(HighlightedLabel::new(bookmark.label().clone(), label_positions))
                         .child(
-                            // FIXME: clone, highlighting
-                            HighlightedLabel::new(String::from(path.to_string_lossy()), Vec::new())
+                            // FIXME: clone
+                            HighlightedLabel::new(String::from(path.to_string_lossy()), path_positions)
                                 .size(LabelSize::Small)
                                 .color(Color::Muted),
                         ),
                 )
                 .when(true, |el| {
-                    let delete_button = div()
+                    let actions = h_flex()
+                        .gap_1()
+                        .child(
+                            IconButton::new("edit", IconName::Pencil)
+                                .icon_size(IconSize::Small)
+                                .on_click(cx.listener(move |this, _event, cx| {
+                                    cx.stop_propagation();
+                                    cx.prevent_default();
+
+                                    this.delegate.edit_bookmark(bookmark_ix, cx)
+                                }))
+                                .tooltip(|cx| Tooltip::text("Edit Bookmark...", cx)),
+                        )
                         .child(
                             IconButton::new("delete", IconName::Close)
                                 .icon_size(IconSize::Small)
@@ -285,16 +599,16 @@ impl PickerDelegate for BookmarkViewDelegate {
                                     cx.stop_propagation();
                                     cx.prevent_default();
 
-                                    this.delegate.delete_bookmark(ix, cx)
+                                    this.delegate.delete_bookmark(bookmark_ix, cx)
                                 }))
                                 .tooltip(|cx| Tooltip::text("Delete Bookmark...", cx)),
                         )
                         .into_any_element();
 
                     if self.selected_index() == ix {
-                        el.end_slot::<AnyElement>(delete_button)
+                        el.end_slot::<AnyElement>(actions)
                     } else {
-                        el.end_hover_slot::<AnyElement>(delete_button)
+                        el.end_hover_slot::<AnyElement>(actions)
                     }
                 }),
         )
@@ -302,4 +616,58 @@ impl PickerDelegate for BookmarkViewDelegate {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use project::WorktreeId;
+    use std::path::Path;
+
+    fn path(worktree: usize, relative_path: &str) -> ProjectPath {
+        ProjectPath {
+            worktree_id: WorktreeId::from_usize(worktree),
+            path: Arc::from(Path::new(relative_path)),
+        }
+    }
+
+    fn bookmark(project_path: ProjectPath, row: u32) -> Bookmark {
+        let abs_path = project_path.path.to_path_buf();
+        Bookmark::new("", project_path, abs_path, Anchor::MIN, Point::new(row, 0))
+    }
+
+    #[test]
+    fn next_bookmark_wraps_forward_past_the_last_bookmark() {
+        let a = bookmark(path(0, "a.rs"), 0);
+        let b = bookmark(path(0, "b.rs"), 0);
+        let bookmarks = vec![a.clone(), b.clone()];
+
+        let current = (b.project_path().clone(), b.point());
+        let found =
+            next_bookmark(&bookmarks, Some(&current), Direction::Next).expect("wraps to a.rs");
+
+        assert_eq!(found.project_path(), a.project_path());
+    }
+
+    #[test]
+    fn next_bookmark_wraps_backward_past_the_first_bookmark() {
+        let a = bookmark(path(0, "a.rs"), 0);
+        let b = bookmark(path(0, "b.rs"), 0);
+        let bookmarks = vec![a.clone(), b.clone()];
+
+        let current = (a.project_path().clone(), a.point());
+        let found =
+            next_bookmark(&bookmarks, Some(&current), Direction::Prev).expect("wraps to b.rs");
+
+        assert_eq!(found.project_path(), b.project_path());
+    }
+
+    #[test]
+    fn split_positions_drops_the_separator_and_shifts_path_positions() {
+        // "todo src/main.rs": the separator space sits right at `label_len`.
+        let label_len = "todo".chars().count();
+        let positions = vec![0, 3, label_len, label_len + 1, label_len + 4];
+
+        let (label_positions, path_positions) = split_positions(label_len, &positions);
+
+        assert_eq!(label_positions, vec![0, 3]);
+        assert_eq!(path_positions, vec![0, 3]);
+    }
+}
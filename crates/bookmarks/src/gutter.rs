@@ -0,0 +1,82 @@
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{AnyElement, AppContext, Model, ViewContext};
+use project::{bookmarks_for_line, Bookmark, Project, ProjectPath};
+use text::{Bias, BufferSnapshot};
+use ui::{prelude::*, Tooltip};
+
+use crate::resolve_and_rebind;
+
+/// Returns the bookmarks (if any) set on `row` of `path`, resolved through
+/// `snapshot` so a bookmark that moved due to edits above it is found at
+/// its current row, the same way diagnostics and breakpoints look up
+/// their own per-line gutter markers.
+pub fn bookmarks_at(
+    project: &Model<Project>,
+    cx: &AppContext,
+    snapshot: &BufferSnapshot,
+    path: &ProjectPath,
+    row: u32,
+) -> Vec<Bookmark> {
+    let bookmarks = project.read(cx).bookmarks().read(cx);
+    bookmarks_for_line(&bookmarks, snapshot, path, row)
+        .cloned()
+        .collect()
+}
+
+/// Installs the bookmark gutter marker on `editor`, alongside diagnostics
+/// and breakpoints: every bookmarked row gets a small icon whose tooltip
+/// shows the bookmark's label, and clicking it re-centers the editor on
+/// that bookmark, the same as picking it from the `BookmarkView` picker.
+pub fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(project) = editor.project(cx) else {
+        return;
+    };
+
+    editor.register_gutter_indicator(move |editor, row, cx| {
+        let path = editor.project_path(cx)?;
+        let buffer = editor.buffer().read(cx).as_singleton()?;
+        let snapshot = buffer.read(cx).snapshot();
+
+        let bookmark = bookmarks_at(&project, cx, &snapshot, &path, row)
+            .into_iter()
+            .next()?;
+        Some(render_marker(project.clone(), bookmark, cx))
+    });
+}
+
+fn render_marker(
+    project: Model<Project>,
+    bookmark: Bookmark,
+    cx: &mut ViewContext<Editor>,
+) -> AnyElement {
+    let label = bookmark.label().clone();
+    let target_path = bookmark.project_path().clone();
+    let target_point = bookmark.point();
+
+    IconButton::new(
+        ("bookmark-gutter-marker", bookmark.point().row as usize),
+        IconName::Bookmark,
+    )
+    .icon_size(IconSize::XSmall)
+    .icon_color(Color::Accent)
+    .tooltip(move |cx| Tooltip::text(label.clone(), cx))
+    .on_click(cx.listener(move |editor, _event, cx| {
+        // Resolve (and, if this bookmark was just restored from
+        // `BOOKMARKS_DB`, bind for the first time) through the anchor, the
+        // same as `BookmarkViewDelegate::confirm` — a bookmark clicked
+        // straight from the gutter may never have gone through the picker.
+        let Some(point) = resolve_and_rebind(&project, &target_path, target_point, editor, cx)
+        else {
+            return;
+        };
+        let point = editor
+            .snapshot(cx)
+            .display_snapshot
+            .buffer_snapshot
+            .clip_point(point, Bias::Left);
+        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+            s.select_ranges([point..point])
+        });
+    }))
+    .into_any_element()
+}
@@ -8,8 +8,11 @@ define_connection! {
     // bookmarks (
     //   bookmark_id: usize, primary key
     //   workspace_id: usize,
-    //   label: String,
     //   project_path: PathBuf,
+    //   abs_path: PathBuf,
+    //   label: String,
+    //   row: u32,
+    //   column: u32,
     // )
     pub static ref BOOKMARKS_DB: BookmarksDb<WorkspaceDb> =
         &[sql! (
@@ -17,7 +20,10 @@ define_connection! {
                 bookmark_id INTEGER PRIMARY KEY,
                 workspace_id INTEGER NOT NULL,
                 project_path BLOB NOT NULL,
+                abs_path BLOB NOT NULL,
                 label TEXT,
+                row INTEGER NOT NULL,
+                column INTEGER NOT NULL,
                 FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
                 ON DELETE CASCADE
                 ON UPDATE CASCADE
@@ -28,19 +34,50 @@ define_connection! {
 
 impl BookmarksDb {
     query! {
-        fn bookmarks(id: WorkspaceId) -> Result<Vec<(String, PathBuf)>> {
-            SELECT label, project_path
+        pub async fn bookmarks(id: WorkspaceId) -> Result<Vec<(i64, String, PathBuf, PathBuf, u32, u32)>> {
+            SELECT bookmark_id, label, project_path, abs_path, row, column
             FROM bookmarks
             WHERE workspace_id IS ?
         }
     }
 
     query! {
-        pub async fn save_bookmark(workspace_id: WorkspaceId, label: String, path: PathBuf) -> Result<()> {
+        pub async fn save_bookmark(
+            workspace_id: WorkspaceId,
+            label: String,
+            project_path: PathBuf,
+            abs_path: PathBuf,
+            row: u32,
+            column: u32
+        ) -> Result<i64> {
             INSERT INTO bookmarks
-                (workspace_id, label, path)
+                (workspace_id, project_path, abs_path, label, row, column)
             VALUES
-                (?1, ?2, ?3)
+                (?1, ?2, ?3, ?4, ?5, ?6)
+            RETURNING bookmark_id
+        }
+    }
+
+    query! {
+        pub async fn delete_bookmark(bookmark_id: i64) -> Result<()> {
+            DELETE FROM bookmarks
+            WHERE bookmark_id IS ?
+        }
+    }
+
+    query! {
+        pub async fn update_bookmark_label(bookmark_id: i64, label: String) -> Result<()> {
+            UPDATE bookmarks
+            SET label = ?2
+            WHERE bookmark_id IS ?1
+        }
+    }
+
+    query! {
+        pub async fn update_bookmark_position(bookmark_id: i64, row: u32, column: u32) -> Result<()> {
+            UPDATE bookmarks
+            SET row = ?2, column = ?3
+            WHERE bookmark_id IS ?1
         }
     }
 }
@@ -1,29 +1,70 @@
 use crate::ProjectPath;
 use std::path::PathBuf;
-use text::Point;
+use text::{Anchor, BufferSnapshot, Point, ToPoint};
+
+/// Identifier assigned to a [`Bookmark`] once it has been persisted to the
+/// `BOOKMARKS_DB`. `None` until the bookmark has been saved for the first time.
+pub type BookmarkId = i64;
 
 #[derive(Clone)]
 pub struct Bookmark {
+    id: Option<BookmarkId>,
     label: String,
     project_path: ProjectPath,
     abs_path: PathBuf,
+    // Buffer anchor for the bookmarked location, so it tracks edits made
+    // above it. `Anchor::MIN` means the bookmark was restored from
+    // `BOOKMARKS_DB` and hasn't been bound to an open buffer yet, in which
+    // case `point` should be used instead.
+    anchor: Anchor,
     point: Point,
 }
 
 impl Bookmark {
-    pub fn new(label: &str, project_path: ProjectPath, abs_path: PathBuf, point: Point) -> Self {
+    pub fn new(
+        label: &str,
+        project_path: ProjectPath,
+        abs_path: PathBuf,
+        anchor: Anchor,
+        point: Point,
+    ) -> Self {
         Self {
+            id: None,
             label: String::from(label),
             project_path,
             abs_path,
+            anchor,
             point,
         }
     }
 
+    /// Creates a bookmark from a persisted row/column, with no live anchor
+    /// yet. Call [`Bookmark::rebind`] once the underlying buffer is open.
+    pub fn from_persisted_point(
+        label: &str,
+        project_path: ProjectPath,
+        abs_path: PathBuf,
+        point: Point,
+    ) -> Self {
+        Self::new(label, project_path, abs_path, Anchor::MIN, point)
+    }
+
+    pub fn id(&self) -> Option<BookmarkId> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: BookmarkId) {
+        self.id = Some(id);
+    }
+
     pub fn label(&self) -> &String {
         &self.label
     }
 
+    pub fn set_label(&mut self, label: &str) {
+        self.label = String::from(label);
+    }
+
     pub fn abs_path(&self) -> &PathBuf {
         &self.abs_path
     }
@@ -32,7 +73,51 @@ impl Bookmark {
         &self.project_path
     }
 
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    /// The last point resolved for this bookmark: the persisted position
+    /// until the anchor is bound to a buffer, then the point stored by the
+    /// latest `rebind`. It is also what bookmarks are looked up and ordered
+    /// by; use `resolve_point` to resolve the current, possibly more up to
+    /// date, point instead.
     pub fn point(&self) -> Point {
         self.point
     }
+
+    /// Resolves this bookmark's current position: through its anchor if
+    /// it's bound to `snapshot`'s buffer, so edits above it are accounted
+    /// for, or the last persisted point otherwise.
+    pub fn resolve_point(&self, snapshot: &BufferSnapshot) -> Point {
+        if self.anchor == Anchor::MIN || !snapshot.can_resolve(&self.anchor) {
+            self.point
+        } else {
+            self.anchor.to_point(snapshot)
+        }
+    }
+
+    /// Binds this bookmark to a live anchor once its buffer has been
+    /// opened (or re-binds it after the anchor moved), storing `point` as
+    /// its resolved location so future edits keep the bookmark pinned to
+    /// the same line and `point()`/persistence stay in sync with it.
+    pub fn rebind(&mut self, anchor: Anchor, point: Point) {
+        self.anchor = anchor;
+        self.point = point;
+    }
+}
+
+/// Looks up the bookmarks for a given file and row, for gutter markers and
+/// other per-line UI that needs to know "is there a bookmark here". Each
+/// bookmark's row is resolved through `resolve_point`, so one that moved
+/// due to edits above it is found at its current row, not a stale one.
+pub fn bookmarks_for_line<'a>(
+    bookmarks: &'a [Bookmark],
+    snapshot: &'a BufferSnapshot,
+    project_path: &'a ProjectPath,
+    row: u32,
+) -> impl Iterator<Item = &'a Bookmark> {
+    bookmarks.iter().filter(move |bookmark| {
+        bookmark.project_path() == project_path && bookmark.resolve_point(snapshot).row == row
+    })
 }